@@ -1,5 +1,10 @@
 use std::convert::TryInto;
 
+pub(crate) mod hash;
+pub mod rc5;
+pub mod utils;
+pub mod word;
+
 type WORD = u32; // Should be 32-bit = 4 bytes
 const W: u32 = 32; // word size in bits
 const R: u32 = 12; // number of rounds
@@ -1,6 +1,21 @@
 use crate::utils;
 use crate::word::{Word, WordBuilder, LargestType};
 
+/// The `Rc5Error`-returning, arbitrary-key-length variant of RC5, kept alongside this
+/// fixed-key-length one while the two converge.
+pub mod cypher;
+/// The RC6 block cipher, which shares `key_schedule` and `magic` with `cypher::Rc5`.
+pub mod rc6;
+/// RustCrypto `cipher` trait adapters for `cypher::Rc5` and `rc6::Rc6`.
+pub mod block_cipher;
+/// Multi-block modes of operation (ECB/CBC/CTR) and PKCS#7 padding for `cypher::Rc5`.
+pub mod modes;
+/// ECB-detection and byte-at-a-time ECB decryption toolkit, built on `modes`.
+pub mod analysis;
+
+pub(crate) mod key_schedule;
+pub(crate) mod magic;
+
 pub struct RC5 {
 	word_size: usize, // number of bits per word
 	num_rounds: u8, // number of encryption/decryption rounds
@@ -0,0 +1,132 @@
+//! Adapters implementing the RustCrypto `cipher` crate traits for [`super::cypher::Rc5`] and
+//! [`super::rc6::Rc6`], so either cipher can be dropped into any `cipher`/`block-modes`-based
+//! construction (CBC, CTR, CFB, OFB, padding, ...) instead of this crate reimplementing them.
+//!
+//! Pinned against `cipher = "0.3"`, whose `BlockEncrypt`/`BlockDecrypt` traits have
+//! `encrypt_block`/`decrypt_block` as their actual required methods (the 0.4 redesign replaced
+//! those with a `BlockBackend`/`encrypt_with_backend` indirection meant for SIMD-parallel
+//! implementations, which these simple adapters don't need).
+//!
+//! `cipher`'s `BlockSize`/`KeySize` are compile-time typenum constants, so each adapter here
+//! fixes the word size and round count it wraps rather than exposing the full runtime
+//! generality of [`super::cypher::Rc5::with_params`].
+
+use cipher::{
+    consts::{U1, U16, U8},
+    errors::InvalidLength,
+    Block, BlockCipher, BlockCipherKey, BlockDecrypt, BlockEncrypt, NewBlockCipher,
+};
+
+use super::cypher::Rc5;
+use super::rc6::Rc6;
+
+/// RC5-32/12/16, the classic parameterization, as a `cipher`-crate block cipher.
+#[allow(non_camel_case_types)]
+pub struct Rc5_32_12_16 {
+    inner: Rc5,
+}
+
+impl BlockCipher for Rc5_32_12_16 {
+    type BlockSize = U8;
+    type ParBlocks = U1;
+}
+
+impl NewBlockCipher for Rc5_32_12_16 {
+    type KeySize = U16;
+
+    fn new(key: &BlockCipherKey<Self>) -> Self {
+        Self::new_from_slice(key).expect("BlockCipherKey<Self> is always KeySize bytes")
+    }
+
+    fn new_from_slice(key: &[u8]) -> Result<Self, InvalidLength> {
+        Rc5::with_params(32, 12, key)
+            .map(|inner| Self { inner })
+            .map_err(|_| InvalidLength)
+    }
+}
+
+impl BlockEncrypt for Rc5_32_12_16 {
+    fn encrypt_block(&self, block: &mut Block<Self>) {
+        let ct = self
+            .inner
+            .encrypt(block.as_slice())
+            .expect("Block<Self> is always BlockSize bytes");
+        block.copy_from_slice(&ct);
+    }
+}
+
+impl BlockDecrypt for Rc5_32_12_16 {
+    fn decrypt_block(&self, block: &mut Block<Self>) {
+        let pt = self
+            .inner
+            .decrypt(block.as_slice())
+            .expect("Block<Self> is always BlockSize bytes");
+        block.copy_from_slice(&pt);
+    }
+}
+
+/// RC6-32/20/16, the AES-finalist parameterization, as a `cipher`-crate block cipher.
+#[allow(non_camel_case_types)]
+pub struct Rc6_32_20_16 {
+    inner: Rc6,
+}
+
+impl BlockCipher for Rc6_32_20_16 {
+    type BlockSize = U16;
+    type ParBlocks = U1;
+}
+
+impl NewBlockCipher for Rc6_32_20_16 {
+    type KeySize = U16;
+
+    fn new(key: &BlockCipherKey<Self>) -> Self {
+        Self::new_from_slice(key).expect("BlockCipherKey<Self> is always KeySize bytes")
+    }
+
+    fn new_from_slice(key: &[u8]) -> Result<Self, InvalidLength> {
+        Rc6::with_params(32, 20, key)
+            .map(|inner| Self { inner })
+            .map_err(|_| InvalidLength)
+    }
+}
+
+impl BlockEncrypt for Rc6_32_20_16 {
+    fn encrypt_block(&self, block: &mut Block<Self>) {
+        let ct = self
+            .inner
+            .encrypt(block.as_slice())
+            .expect("Block<Self> is always BlockSize bytes");
+        block.copy_from_slice(&ct);
+    }
+}
+
+impl BlockDecrypt for Rc6_32_20_16 {
+    fn decrypt_block(&self, block: &mut Block<Self>) {
+        let pt = self
+            .inner
+            .decrypt(block.as_slice())
+            .expect("Block<Self> is always BlockSize bytes");
+        block.copy_from_slice(&pt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_cipher_traits() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F,
+        ];
+        let rc5 = Rc5_32_12_16::new_from_slice(&key).unwrap();
+        let mut block = Block::<Rc5_32_12_16>::clone_from_slice(&[
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77,
+        ]);
+        let plaintext = block;
+        rc5.encrypt_block(&mut block);
+        rc5.decrypt_block(&mut block);
+        assert_eq!(block, plaintext);
+    }
+}
@@ -1,161 +1,222 @@
-use std::cmp::max;
-use std::convert::TryInto;
+use crate::word::{Word, WordBuilder};
+
+use super::key_schedule;
+use super::magic;
+use super::modes::{self, Mode};
+
 #[derive(Debug)]
 pub enum Rc5Error {
     InvalidKeyLen,
+    InvalidWordSize,
     BufferOutOfBounds,
-}
-
-#[derive(Debug)]
-pub enum Rc5Version {
-    Rc5_32_12_16,
-    Rc5_32_16_16,
+    /// CBC/CTR was used without an IV, or the IV's length didn't match the block size.
+    InvalidIvLen,
+    /// PKCS#7 padding was missing or malformed on decrypt.
+    PaddingError,
 }
 
 pub struct Rc5 {
     /// The expanded secret key table.
-    secret_key_table: Vec<u32>,
+    secret_key_table: Vec<Word>,
 
-    /// The word size in bits.
-    word_size: u32,
+    /// Builds words of the configured width.
+    word_builder: WordBuilder,
 
-    /// The number of rounds
-    num_rounds: usize,
+    /// The word size in bits (one of `SUPPORTED_WORD_SIZES`).
+    word_size: usize,
 
-    /// The key length
-    key_len: usize,
+    /// The number of rounds.
+    num_rounds: u8,
 
-    /// Magic Constants determined by the size of W.
-    magic_constant_p: u32,
-    magic_constant_q: u32,
+    /// The key length in bytes.
+    key_len: usize,
 
     /// Number of bytes in each word.
     bytes_per_word: usize,
 }
 
 impl Rc5 {
-    pub fn new(key: &[u8], version: Rc5Version) -> Result<Self, Rc5Error> {
-        let (num_rounds, word_size, key_len, magic_constant_p, magic_constant_q) = match version {
-            Rc5Version::Rc5_32_12_16 => (12, 32, 16, 0xB7E15163, 0x9E3779B9),
-            Rc5Version::Rc5_32_16_16 => (16, 32, 16, 0xB7E15163, 0x9E3779B9),
-        };
-
-        if key.len() != key_len {
+    /// Word sizes this implementation knows how to derive magic constants for.
+    const SUPPORTED_WORD_SIZES: [usize; 5] = magic::SUPPORTED_WORD_SIZES;
+
+    /// Builds an RC5-`word_size`/`num_rounds`/`key.len()` instance, replacing the old
+    /// fixed `Rc5Version` table with a constructor that works for any word size in
+    /// `SUPPORTED_WORD_SIZES`, any round count and any key length up to 255 bytes.
+    pub fn with_params(word_size: usize, num_rounds: u8, key: &[u8]) -> Result<Self, Rc5Error> {
+        if !Self::SUPPORTED_WORD_SIZES.contains(&word_size) {
+            return Err(Rc5Error::InvalidWordSize);
+        }
+        if key.len() > u8::MAX as usize {
             return Err(Rc5Error::InvalidKeyLen);
         }
 
-        let bytes_per_word = (word_size / 8) as usize;
-        let mut rc5 = Self {
-            secret_key_table: vec![],
-            word_size,
-            num_rounds,
-            key_len,
+        let word_builder = magic::word_builder_for(word_size);
+        let (magic_constant_p, magic_constant_q) = magic::magic_constants(&word_builder, word_size);
+
+        let bytes_per_word = word_size / 8;
+        let table_size = ((num_rounds as usize) + 1) * 2;
+        let secret_key_table = key_schedule::expand_key(
+            &word_builder,
+            key,
+            bytes_per_word,
+            table_size,
             magic_constant_p,
             magic_constant_q,
-            bytes_per_word,
-        };
-        rc5.expand_key(key);
+        );
 
-        Ok(rc5)
+        Ok(Self {
+            secret_key_table,
+            word_builder,
+            word_size,
+            num_rounds,
+            key_len: key.len(),
+            bytes_per_word,
+        })
     }
 
-    fn expand_key(&mut self, key: &[u8]) {
-        let num_blocks = max(self.key_len as usize, 1) / self.bytes_per_word;
-
-        let mut l: Vec<u32> = vec![0; self.key_len - 1];
-        for (i, b) in key.iter().enumerate().rev() {
-            l[i / self.bytes_per_word] =
-                (l[i / self.bytes_per_word].checked_shl(8).unwrap_or(0)).wrapping_add(*b as u32);
-        }
+    /// The block size in bytes handled by `encrypt`/`decrypt`, i.e. `2 * word_size / 8`.
+    pub fn block_size(&self) -> usize {
+        2 * self.bytes_per_word
+    }
 
-        let num_words = ((self.num_rounds + 1) * 2) as usize;
-        let mut secret_key_table = vec![0; num_words];
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, Rc5Error> {
+        let words = self.le_bytes_to_words(plaintext)?;
+        let mut a = words[0] + self.secret_key_table[0];
+        let mut b = words[1] + self.secret_key_table[1];
 
-        secret_key_table[0] = self.magic_constant_p;
-        for i in 1..num_words {
-            secret_key_table[i] = secret_key_table[i - 1].wrapping_add(self.magic_constant_q);
+        for i in 1..=(self.num_rounds as usize) {
+            a = ((a ^ b) << b) + self.secret_key_table[2 * i];
+            b = ((b ^ a) << a) + self.secret_key_table[2 * i + 1];
         }
 
-        // Mix the secret key.
-        let mut i = 0;
-        let mut j = 0;
-
-        let mut a: u32 = 0;
-        let mut b: u32 = 0;
-
-        for _ in 0..max(num_words, num_blocks) * 3 {
-            secret_key_table[i] =
-                self.rotate_left(secret_key_table[i].wrapping_add(a.wrapping_add(b)), 3);
-            a = secret_key_table[i];
+        Ok(self.words_to_le_bytes(&[a, b]))
+    }
 
-            let a_b = a.wrapping_add(b);
-            l[j] = self.rotate_left(l[j].wrapping_add(a_b), a_b);
-            b = l[j];
+    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Rc5Error> {
+        let words = self.le_bytes_to_words(ciphertext)?;
+        let mut a = words[0];
+        let mut b = words[1];
 
-            i = (i + 1) % num_words;
-            j = (j + 1) % num_blocks;
+        for i in (1..=(self.num_rounds as usize)).rev() {
+            b = ((b - self.secret_key_table[2 * i + 1]) >> a) ^ a;
+            a = ((a - self.secret_key_table[2 * i]) >> b) ^ b;
         }
+        b = b - self.secret_key_table[1];
+        a = a - self.secret_key_table[0];
 
-        self.secret_key_table = secret_key_table;
+        Ok(self.words_to_le_bytes(&[a, b]))
     }
 
-    pub fn encrypt(&self, plaintext: Vec<u8>) -> Result<Vec<u8>, Rc5Error> {
-        let words = self.le_bytes_to_words(&plaintext)?;
-        let mut a = words[0].wrapping_add(self.secret_key_table[0]);
-        let mut b = words[1].wrapping_add(self.secret_key_table[1]);
-
-        for i in 1..=self.num_rounds {
-            a = self
-                .rotate_left(a ^ b, b)
-                .wrapping_add(self.secret_key_table[2 * i]);
-            b = self
-                .rotate_left(b ^ a, a)
-                .wrapping_add(self.secret_key_table[2 * i + 1]);
+    /// Encrypts `data` of any length, under PKCS#7 padding and the given `mode`. `iv` is
+    /// required (and must be exactly `block_size()` bytes) for `Mode::Cbc`/`Mode::Ctr`.
+    pub fn encrypt_message(
+        &self,
+        data: &[u8],
+        mode: Mode,
+        iv: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Rc5Error> {
+        let block_size = self.block_size();
+        let padded = modes::pkcs7_pad(data, block_size);
+        let mut out = Vec::with_capacity(padded.len());
+
+        match mode {
+            Mode::Ecb => {
+                for block in padded.chunks(block_size) {
+                    out.extend(self.encrypt(block)?);
+                }
+            }
+            Mode::Cbc => {
+                let mut prev = self.iv_or_err(iv, block_size)?;
+                for block in padded.chunks(block_size) {
+                    let mut xored = block.to_vec();
+                    modes::xor_in_place(&mut xored, &prev);
+                    let ct = self.encrypt(&xored)?;
+                    out.extend_from_slice(&ct);
+                    prev = ct;
+                }
+            }
+            Mode::Ctr => {
+                let mut counter = self.iv_or_err(iv, block_size)?;
+                for block in padded.chunks(block_size) {
+                    let mut xored = block.to_vec();
+                    modes::xor_in_place(&mut xored, &self.encrypt(&counter)?);
+                    out.extend(xored);
+                    modes::increment_counter(&mut counter);
+                }
+            }
         }
 
-        Ok(self.words_to_le_bytes(&[a, b]))
+        Ok(out)
     }
 
-    fn rotate_left(&self, x: u32, y: u32) -> u32 {
-        x.wrapping_shl((y & (self.word_size - 1)) as u32)
-            | x.wrapping_shr((self.word_size - (y & (self.word_size - 1))) as u32)
-    }
+    /// Inverts [`Rc5::encrypt_message`], validating and stripping the PKCS#7 padding.
+    pub fn decrypt_message(
+        &self,
+        data: &[u8],
+        mode: Mode,
+        iv: Option<&[u8]>,
+    ) -> Result<Vec<u8>, Rc5Error> {
+        let block_size = self.block_size();
+        if data.is_empty() || data.len() % block_size != 0 {
+            return Err(Rc5Error::BufferOutOfBounds);
+        }
+        let mut padded = Vec::with_capacity(data.len());
+
+        match mode {
+            Mode::Ecb => {
+                for block in data.chunks(block_size) {
+                    padded.extend(self.decrypt(block)?);
+                }
+            }
+            Mode::Cbc => {
+                let mut prev = self.iv_or_err(iv, block_size)?;
+                for block in data.chunks(block_size) {
+                    let mut pt = self.decrypt(block)?;
+                    modes::xor_in_place(&mut pt, &prev);
+                    padded.extend(pt);
+                    prev = block.to_vec();
+                }
+            }
+            Mode::Ctr => {
+                let mut counter = self.iv_or_err(iv, block_size)?;
+                for block in data.chunks(block_size) {
+                    let mut pt = block.to_vec();
+                    modes::xor_in_place(&mut pt, &self.encrypt(&counter)?);
+                    padded.extend(pt);
+                    modes::increment_counter(&mut counter);
+                }
+            }
+        }
 
-    fn rotate_right(&self, x: u32, y: u32) -> u32 {
-        x.wrapping_shr((y & (self.word_size - 1)) as u32)
-            | x.wrapping_shl((self.word_size - (y & (self.word_size - 1))) as u32)
+        modes::pkcs7_unpad(&padded, block_size)
     }
 
-    pub fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, Rc5Error> {
-        let words = self.le_bytes_to_words(&ciphertext)?;
-        let mut b = words[1];
-        let mut a = words[0];
-
-        for i in (1..=self.num_rounds).rev() {
-            b = self.rotate_right(b.wrapping_sub(self.secret_key_table[2 * i + 1]), a) ^ a;
-            a = self.rotate_right(a.wrapping_sub(self.secret_key_table[2 * i]), b) ^ b;
+    fn iv_or_err(&self, iv: Option<&[u8]>, block_size: usize) -> Result<Vec<u8>, Rc5Error> {
+        match iv {
+            Some(iv) if iv.len() == block_size => Ok(iv.to_vec()),
+            _ => Err(Rc5Error::InvalidIvLen),
         }
-        b = b.wrapping_sub(self.secret_key_table[1]);
-        a = a.wrapping_sub(self.secret_key_table[0]);
-
-        Ok(self.words_to_le_bytes(&[a, b]))
     }
 
-    fn le_bytes_to_words(&self, block: &[u8]) -> Result<[u32; 2], Rc5Error> {
-        if block.len() < self.bytes_per_word {
+    fn le_bytes_to_words(&self, block: &[u8]) -> Result<[Word; 2], Rc5Error> {
+        if block.len() < self.block_size() {
             return Err(Rc5Error::BufferOutOfBounds);
         }
 
-        let mut word_buf = [0u32; 2];
-        word_buf[0] = u32::from_le_bytes(block[..self.bytes_per_word].try_into().unwrap());
-        word_buf[1] = u32::from_le_bytes(block[self.bytes_per_word..].try_into().unwrap());
-        Ok(word_buf)
+        let mut words = [self.word_builder.build_word(0); 2];
+        for (w, chunk) in words.iter_mut().zip(block.chunks(self.bytes_per_word)) {
+            for byte in chunk.iter().rev() {
+                *w = (*w << 8_u8) + *byte;
+            }
+        }
+        Ok(words)
     }
 
-    fn words_to_le_bytes(&self, words: &[u32; 2]) -> Vec<u8> {
-        let mut bytes: Vec<u8> = Vec::new();
-        bytes.extend_from_slice(&words[0].to_le_bytes());
-        bytes.extend_from_slice(&words[1].to_le_bytes());
+    fn words_to_le_bytes(&self, words: &[Word; 2]) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(2 * self.bytes_per_word);
+        for w in words {
+            bytes.extend(w.to_le_bytes());
+        }
         bytes
     }
 }
@@ -172,39 +233,138 @@ mod tests {
         let pt = vec![0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
         let ct = vec![0x2D, 0xDC, 0x14, 0x9B, 0xCF, 0x08, 0x8B, 0x9E];
 
-        let rc5 = Rc5::new(&key, Rc5Version::Rc5_32_12_16).unwrap();
-        let res = rc5.encrypt(pt.clone()).unwrap();
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        let res = rc5.encrypt(&pt).unwrap();
 
         assert_eq!(ct, res);
     }
+
     #[test]
     fn encode_b() {
-    	let key = vec![0x2B, 0xD6, 0x45, 0x9F, 0x82, 0xC5, 0xB3, 0x00, 0x95, 0x2C, 0x49, 0x10, 0x48, 0x81, 0xFF, 0x48];
-    	let pt  = vec![0xEA, 0x02, 0x47, 0x14, 0xAD, 0x5C, 0x4D, 0x84];
-    	let ct  = vec![0x11, 0xE4, 0x3B, 0x86, 0xD2, 0x31, 0xEA, 0x64];
-        let rc5 = Rc5::new(&key, Rc5Version::Rc5_32_12_16).unwrap();
-        let res = rc5.encrypt(pt.clone()).unwrap();
+        let key = vec![
+            0x2B, 0xD6, 0x45, 0x9F, 0x82, 0xC5, 0xB3, 0x00, 0x95, 0x2C, 0x49, 0x10, 0x48, 0x81,
+            0xFF, 0x48,
+        ];
+        let pt = vec![0xEA, 0x02, 0x47, 0x14, 0xAD, 0x5C, 0x4D, 0x84];
+        let ct = vec![0x11, 0xE4, 0x3B, 0x86, 0xD2, 0x31, 0xEA, 0x64];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        let res = rc5.encrypt(&pt).unwrap();
 
-    	assert!(&ct[..] == &res[..]);
+        assert!(&ct[..] == &res[..]);
     }
 
     #[test]
     fn decode_a() {
-    	let key = vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F];
-    	let pt  = vec![0x96, 0x95, 0x0D, 0xDA, 0x65, 0x4A, 0x3D, 0x62];
-    	let ct  = vec![0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
-        let rc5 = Rc5::new(&key, Rc5Version::Rc5_32_12_16).unwrap();
+        let key = vec![
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F,
+        ];
+        let pt = vec![0x96, 0x95, 0x0D, 0xDA, 0x65, 0x4A, 0x3D, 0x62];
+        let ct = vec![0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
         let res = rc5.decrypt(&ct).unwrap();
-    	assert!(&pt[..] == &res[..]);
+        assert!(&pt[..] == &res[..]);
     }
 
     #[test]
     fn decode_b() {
-    	let key = vec![0x2B, 0xD6, 0x45, 0x9F, 0x82, 0xC5, 0xB3, 0x00, 0x95, 0x2C, 0x49, 0x10, 0x48, 0x81, 0xFF, 0x48];
-    	let pt  = vec![0x63, 0x8B, 0x3A, 0x5E, 0xF7, 0x2B, 0x66, 0x3F];
-    	let ct  = vec![0xEA, 0x02, 0x47, 0x14, 0xAD, 0x5C, 0x4D, 0x84];
-        let rc5 = Rc5::new(&key, Rc5Version::Rc5_32_12_16).unwrap();
+        let key = vec![
+            0x2B, 0xD6, 0x45, 0x9F, 0x82, 0xC5, 0xB3, 0x00, 0x95, 0x2C, 0x49, 0x10, 0x48, 0x81,
+            0xFF, 0x48,
+        ];
+        let pt = vec![0x63, 0x8B, 0x3A, 0x5E, 0xF7, 0x2B, 0x66, 0x3F];
+        let ct = vec![0xEA, 0x02, 0x47, 0x14, 0xAD, 0x5C, 0x4D, 0x84];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
         let res = rc5.decrypt(&ct).unwrap();
-    	assert!(&pt[..] == &res[..]);
+        assert!(&pt[..] == &res[..]);
+    }
+
+    #[test]
+    fn round_trip_non_32_bit_words() {
+        let key = vec![0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+        for &word_size in &Rc5::SUPPORTED_WORD_SIZES {
+            let rc5 = Rc5::with_params(word_size, 12, &key).unwrap();
+            let pt = vec![0_u8; rc5.block_size()];
+            let ct = rc5.encrypt(&pt).unwrap();
+            let res = rc5.decrypt(&ct).unwrap();
+            assert_eq!(pt, res);
+        }
+    }
+
+    #[test]
+    fn ecb_round_trip_multi_block() {
+        let key = vec![0x00_u8; 16];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        let data = b"this message spans more than one 8-byte RC5 block".to_vec();
+
+        let ct = rc5.encrypt_message(&data, Mode::Ecb, None).unwrap();
+        assert_eq!(ct.len() % rc5.block_size(), 0);
+        let pt = rc5.decrypt_message(&ct, Mode::Ecb, None).unwrap();
+        assert_eq!(pt, data);
+    }
+
+    #[test]
+    fn cbc_round_trip_and_iv_sensitivity() {
+        let key = vec![0x00_u8; 16];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        let data = b"repeatedrepeatedrepeated blocks".to_vec();
+        let iv = vec![0x11_u8; rc5.block_size()];
+
+        let ct = rc5.encrypt_message(&data, Mode::Cbc, Some(&iv)).unwrap();
+        let pt = rc5.decrypt_message(&ct, Mode::Cbc, Some(&iv)).unwrap();
+        assert_eq!(pt, data);
+
+        // Unlike ECB, repeated plaintext blocks must not yield repeated ciphertext blocks.
+        let block_size = rc5.block_size();
+        assert_ne!(&ct[..block_size], &ct[block_size..2 * block_size]);
+
+        // A different IV changes the ciphertext.
+        let other_iv = vec![0x22_u8; block_size];
+        let other_ct = rc5.encrypt_message(&data, Mode::Cbc, Some(&other_iv)).unwrap();
+        assert_ne!(ct, other_ct);
+    }
+
+    #[test]
+    fn ctr_round_trip() {
+        let key = vec![0x00_u8; 16];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        let data = b"counter mode turns a block cipher into a stream".to_vec();
+        let iv = vec![0x00_u8; rc5.block_size()];
+
+        let ct = rc5.encrypt_message(&data, Mode::Ctr, Some(&iv)).unwrap();
+        let pt = rc5.decrypt_message(&ct, Mode::Ctr, Some(&iv)).unwrap();
+        assert_eq!(pt, data);
+    }
+
+    #[test]
+    fn cbc_without_iv_is_an_error() {
+        let key = vec![0x00_u8; 16];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        assert!(matches!(
+            rc5.encrypt_message(b"data", Mode::Cbc, None),
+            Err(Rc5Error::InvalidIvLen)
+        ));
+    }
+
+    #[test]
+    fn decrypt_rejects_bad_padding() {
+        let key = vec![0x00_u8; 16];
+        let rc5 = Rc5::with_params(32, 12, &key).unwrap();
+        let mut ct = rc5.encrypt_message(b"hello world", Mode::Ecb, None).unwrap();
+        let last = ct.len() - 1;
+        ct[last] ^= 0xFF;
+        assert!(matches!(
+            rc5.decrypt_message(&ct, Mode::Ecb, None),
+            Err(Rc5Error::PaddingError)
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_word_size() {
+        let key = vec![0x00];
+        assert!(matches!(
+            Rc5::with_params(24, 12, &key),
+            Err(Rc5Error::InvalidWordSize)
+        ));
     }
 }
@@ -0,0 +1,47 @@
+use crate::utils;
+use crate::word::{Word, WordBuilder};
+
+/// The RC5/RC6 key-expansion routine: builds the `S` table of `table_size` words from the
+/// magic constants and mixes the secret key `key` into it. Shared by [`super::cypher::Rc5`]
+/// and [`super::rc6::Rc6`], which only differ in how big `table_size` is and how the table
+/// is consumed by the round function.
+pub(crate) fn expand_key(
+    word_builder: &WordBuilder,
+    key: &[u8],
+    bytes_per_word: usize,
+    table_size: usize,
+    magic_constant_p: Word,
+    magic_constant_q: Word,
+) -> Vec<Word> {
+    let num_words = std::cmp::max(utils::div_ceil(key.len(), bytes_per_word), 1);
+
+    let mut l = word_builder.new_word_vec(num_words);
+    for (i, b) in key.iter().enumerate().rev() {
+        l[i / bytes_per_word] = (l[i / bytes_per_word] << 8_u8) + *b;
+    }
+
+    let mut s = word_builder.new_word_vec(table_size);
+    s[0] = magic_constant_p;
+    for i in 1..table_size {
+        s[i] = s[i - 1] + magic_constant_q;
+    }
+
+    // Mix the secret key.
+    let mut a = word_builder.build_word(0);
+    let mut b = word_builder.build_word(0);
+    let mut i = 0;
+    let mut j = 0;
+
+    for _ in 0..3 * std::cmp::max(table_size, num_words) {
+        s[i] = (s[i] + (a + b)) << 3_u8;
+        a = s[i];
+
+        l[j] = (l[j] + (a + b)) << (a + b);
+        b = l[j];
+
+        i = (i + 1) % table_size;
+        j = (j + 1) % num_words;
+    }
+
+    s
+}
@@ -0,0 +1,28 @@
+use crate::utils::BigNum;
+use crate::word::{LargestType, Word, WordBuilder};
+
+/// Word sizes `Rc5`/`Rc6` accept, i.e. the ones this module can turn into a `Word`.
+///
+/// `BigNum::magic_constants_bignum` itself has no upper bound - it derives exact `(P_w, Q_w)`
+/// values for `WordType::Big`'s 256/512-bit widths too (see `calc_magic_consts_test` in
+/// `utils.rs`). What's missing to actually offer RC5/RC6-256/512 through this cipher is
+/// everything *downstream* of the constant: `to_u128` below can only narrow a value known to
+/// fit in 128 bits, `Word`'s multiplication (used by RC6's round function) isn't `Big`-aware,
+/// and `block_cipher.rs`'s adapters are wired to fixed typenum block sizes. Until that's done,
+/// widening this list would accept a word size whose cipher silently corrupts its output -
+/// so it stays capped at 128 and 256/512 remain future work.
+pub(crate) const SUPPORTED_WORD_SIZES: [usize; 5] = [8, 16, 32, 64, 128];
+
+/// Derives the `(P_w, Q_w)` magic constants for `word_size` bits using `word_builder`. Only
+/// valid for `word_size` in `SUPPORTED_WORD_SIZES` (`to_u128` below requires `word_size <= 128`).
+pub(crate) fn magic_constants(word_builder: &WordBuilder, word_size: usize) -> (Word, Word) {
+    let (p, q) = BigNum::magic_constants_bignum(word_size as u32);
+    (
+        word_builder.build_word(p.to_u128()),
+        word_builder.build_word(q.to_u128()),
+    )
+}
+
+pub(crate) fn word_builder_for(word_size: usize) -> WordBuilder {
+    WordBuilder::new(word_size as LargestType)
+}
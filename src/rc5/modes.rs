@@ -0,0 +1,59 @@
+use super::cypher::Rc5Error;
+
+/// Block cipher mode of operation supported by [`super::cypher::Rc5::encrypt_message`] and
+/// [`super::cypher::Rc5::decrypt_message`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Each block encrypted independently. Deterministic and pattern-leaking; kept mainly so
+    /// the analysis toolkit in `analysis` has something unsafe to detect.
+    Ecb,
+    /// Each plaintext block is XORed with the previous ciphertext block (the IV for the
+    /// first) before encryption.
+    Cbc,
+    /// An incrementing counter block is encrypted and XORed with the plaintext, turning the
+    /// block cipher into a stream cipher.
+    Ctr,
+}
+
+/// Appends PKCS#7 padding: `n` bytes each equal to `n`, where
+/// `n = block_size - (data.len() % block_size)` (a full extra block when already aligned).
+pub(crate) fn pkcs7_pad(data: &[u8], block_size: usize) -> Vec<u8> {
+    let pad_len = block_size - (data.len() % block_size);
+    let mut padded = Vec::with_capacity(data.len() + pad_len);
+    padded.extend_from_slice(data);
+    padded.extend(std::iter::repeat(pad_len as u8).take(pad_len));
+    padded
+}
+
+/// Validates and strips PKCS#7 padding: the final byte `n` must be in `1..=block_size` and
+/// the last `n` bytes must all equal `n`.
+pub(crate) fn pkcs7_unpad(data: &[u8], block_size: usize) -> Result<Vec<u8>, Rc5Error> {
+    let pad_len = *data.last().ok_or(Rc5Error::PaddingError)? as usize;
+    if pad_len == 0 || pad_len > block_size || pad_len > data.len() {
+        return Err(Rc5Error::PaddingError);
+    }
+    if !data[data.len() - pad_len..]
+        .iter()
+        .all(|&b| b as usize == pad_len)
+    {
+        return Err(Rc5Error::PaddingError);
+    }
+    Ok(data[..data.len() - pad_len].to_vec())
+}
+
+pub(crate) fn xor_in_place(block: &mut [u8], other: &[u8]) {
+    for (b, o) in block.iter_mut().zip(other) {
+        *b ^= o;
+    }
+}
+
+/// Increments a counter block (used by CTR mode) in place, treating it as a big-endian
+/// integer and wrapping on overflow.
+pub(crate) fn increment_counter(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
@@ -1,24 +1,206 @@
+use std::cmp::Ordering;
+use std::ops::{Add, Mul, Sub};
 
-
-pub struct BigNum(String);
+/// Arbitrary-precision unsigned integer, stored as little-endian base-2^32 limbs (with `u64`
+/// as the double-width accumulator for carries). Replaces the previous decimal-`String`
+/// representation, whose `multiply` re-did schoolbook digit math on ASCII bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigNum(Vec<u32>);
 
 impl BigNum {
-    pub fn new(num: &str) -> Self {
-        BigNum(String::from(num))
+    pub fn zero() -> Self {
+        BigNum(vec![0])
+    }
+
+    pub fn from_u64(value: u64) -> Self {
+        let mut limbs = vec![value as u32, (value >> 32) as u32];
+        Self::trim(&mut limbs);
+        BigNum(limbs)
+    }
+
+    /// Parses an unsigned decimal digit string into a `BigNum`.
+    pub fn from_dec_str(num: &str) -> Self {
+        let ten = BigNum::from_u64(10);
+        num.bytes().fold(BigNum::zero(), |acc, b| {
+            let digit = (b as char)
+                .to_digit(10)
+                .expect("from_dec_str: input must be a decimal digit string");
+            acc * ten.clone() + BigNum::from_u64(digit as u64)
+        })
+    }
+
+    /// Renders `self` in the given `radix` (2..=16).
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        assert!((2..=16).contains(&radix));
+
+        if self.is_zero() {
+            return "0".to_string();
+        }
+
+        let base = BigNum::from_u64(radix as u64);
+        let mut value = self.clone();
+        let mut digits = Vec::new();
+        while !value.is_zero() {
+            let (quotient, remainder) = value.div_rem(&base);
+            digits.push(std::char::from_digit(remainder.0[0], radix).unwrap());
+            value = quotient;
+        }
+
+        digits.iter().rev().collect()
     }
 
     pub fn to_string(&self) -> String {
-        self.0.clone()
+        self.to_str_radix(10)
     }
 
-    /// Returns an String containing the Integer part. Doesn't change the object state.
-    pub fn truncate(&self) -> String {
-        let ret : Vec<&str> = self.0.split('.').collect();
+    pub fn to_binary_string(&self) -> String {
+        self.to_str_radix(2)
+    }
+
+    pub fn to_hex_string(&self) -> String {
+        self.to_str_radix(16)
+    }
+
+    /// Classic long division over limbs, processed one bit of `self` at a time:
+    /// returns `(self / divisor, self % divisor)`.
+    pub fn div_rem(&self, divisor: &BigNum) -> (BigNum, BigNum) {
+        assert!(!divisor.is_zero(), "division by zero");
+
+        let mut quotient = vec![0_u32; self.0.len()];
+        let mut remainder = BigNum::zero();
+
+        for i in (0..self.0.len() * 32).rev() {
+            remainder = remainder.shl1();
+            if (self.0[i / 32] >> (i % 32)) & 1 == 1 {
+                remainder.0[0] |= 1;
+            }
+            if remainder.cmp(divisor) != Ordering::Less {
+                remainder = remainder - divisor.clone();
+                quotient[i / 32] |= 1 << (i % 32);
+            }
+        }
+
+        Self::trim(&mut quotient);
+        (BigNum(quotient), remainder)
+    }
+
+    /// Extra bits of fixed-point precision carried above `w` while accumulating the series
+    /// below, to absorb rounding error before the final truncation back to `w` bits.
+    const MAGIC_CONSTANT_GUARD_BITS: u32 = 64;
+
+    /// Derives RC5/RC6's magic constants `P_w = Odd((e - 2) * 2^w)` and
+    /// `Q_w = Odd((phi - 1) * 2^w)` directly at `w` bits of precision, for any `w`, using a
+    /// fixed-point scale `S = 2^(w+g)` (guard `g` bits): `e` via its Taylor series
+    /// `sum(1/k!)` and `phi` via an integer square root of `5 * S^2`. This replaces the
+    /// previous approach of truncating a fixed 128-bit hardcoded binary expansion, which
+    /// silently lost precision past `w = 128`.
+    pub fn magic_constants_bignum(w: u32) -> (BigNum, BigNum) {
+        let guard = Self::MAGIC_CONSTANT_GUARD_BITS;
+        let scale = BigNum::one_shl(w + guard);
+
+        let e_minus_2_scaled = Self::e_minus_2_times_scale(&scale);
+        let phi_minus_1_scaled = Self::phi_minus_1_times_scale(&scale);
+
+        (
+            Self::round_to_width_and_force_odd(e_minus_2_scaled, guard),
+            Self::round_to_width_and_force_odd(phi_minus_1_scaled, guard),
+        )
+    }
 
-        if let Some(value) = ret.get(0) {
-            return value.to_string();
+    /// `floor((e - 2) * scale)`, via the Taylor series `e = sum(1/k!)`: starting from the
+    /// `k = 0` term `t = scale`, each step divides the running term by `k` (so `t` becomes
+    /// `scale/k!`) and accumulates it, until the term underflows to zero.
+    fn e_minus_2_times_scale(scale: &BigNum) -> BigNum {
+        let mut sum = scale.clone();
+        let mut term = scale.clone();
+        let mut k = 1_u64;
+        while !term.is_zero() {
+            term = term.div_rem(&BigNum::from_u64(k)).0;
+            sum = sum + term.clone();
+            k += 1;
         }
-        return self.0.clone();
+        sum - (scale.clone() + scale.clone())
+    }
+
+    /// `floor((phi - 1) * scale)`, using `phi - 1 = (sqrt(5) - 1) / 2` and
+    /// `floor(sqrt(5) * scale) = isqrt(5 * scale^2)`.
+    fn phi_minus_1_times_scale(scale: &BigNum) -> BigNum {
+        let five_scale_squared =
+            BigNum::from_u64(5) * scale.clone() * scale.clone();
+        let sqrt5_scaled = Self::isqrt(&five_scale_squared);
+        (sqrt5_scaled - scale.clone()).div_rem(&BigNum::from_u64(2)).0
+    }
+
+    /// Integer square root via Newton's method: `x <- (x + n/x) / 2`, starting from a
+    /// power-of-two upper bound, until the iterate stops decreasing.
+    fn isqrt(n: &BigNum) -> BigNum {
+        if n.is_zero() {
+            return BigNum::zero();
+        }
+
+        let mut x = BigNum::one_shl(n.bit_length() / 2 + 1);
+        loop {
+            let (quotient, _remainder) = n.div_rem(&x);
+            let next = (x.clone() + quotient).div_rem(&BigNum::from_u64(2)).0;
+            if next.cmp(&x) != Ordering::Less {
+                return x;
+            }
+            x = next;
+        }
+    }
+
+    /// Narrows the `w + guard`-bit fixed-point `value` (i.e. `floor(x * 2^(w+guard))` for the
+    /// real number `x` this constant derives from) down to `Odd(x)`, "the odd integer nearest
+    /// `x`" per the RC5 spec.
+    ///
+    /// `base = floor(x)` is always within 1 of `x`, so whichever odd integer is closest to `x`
+    /// must be `base` itself or one of its immediate odd neighbours `base - 1`/`base + 1`. If
+    /// `base` is already odd it wins outright: `x` is in `[base, base + 1)`, which is strictly
+    /// closer to `base` than to `base`'s other odd neighbours (`base - 2`/`base + 2`). If `base`
+    /// is even, `x` is in `[base, base + 1)` too, which is at most distance 1 from `base + 1`
+    /// and at least distance 1 from `base - 1` - so `base + 1` wins (ties, i.e. `x == base`
+    /// exactly, round up).
+    fn round_to_width_and_force_odd(value: BigNum, guard: u32) -> BigNum {
+        let (base, _fraction) = value.div_rem(&BigNum::one_shl(guard));
+
+        let (_, parity) = base.div_rem(&BigNum::from_u64(2));
+        if parity.is_zero() {
+            base + BigNum::from_u64(1)
+        } else {
+            base
+        }
+    }
+
+    /// `1 << bits`, built directly from limbs rather than by repeated doubling.
+    pub fn one_shl(bits: u32) -> BigNum {
+        let mut limbs = vec![0_u32; (bits / 32) as usize + 1];
+        limbs[(bits / 32) as usize] = 1_u32 << (bits % 32);
+        BigNum(limbs)
+    }
+
+    /// Number of bits needed to represent `self` (0 for zero itself).
+    pub fn bit_length(&self) -> u32 {
+        if self.is_zero() {
+            return 0;
+        }
+        let mut limbs = self.0.clone();
+        Self::trim(&mut limbs);
+        let top = *limbs.last().unwrap();
+        (limbs.len() as u32 - 1) * 32 + (32 - top.leading_zeros())
+    }
+
+    /// Narrows `self` into a `u128`. Only valid when `self` actually fits in 128 bits (e.g.
+    /// magic constants at `w <= 128`) - callers above `w = 128` must go through `WordType::Big`
+    /// instead, which this does not produce.
+    pub fn to_u128(&self) -> u128 {
+        debug_assert!(
+            self.bit_length() <= 128,
+            "to_u128: value doesn't fit in 128 bits, would be silently truncated"
+        );
+        self.0
+            .iter()
+            .rev()
+            .fold(0_u128, |acc, &limb| (acc << 32) | limb as u128)
     }
 
     pub fn convert_binary_to_hex(binary: &String) -> String {
@@ -57,87 +239,6 @@ impl BigNum {
 
     }
 
-    pub fn calc_magic_constants(w: u32) {
-        // Value of 'e' extracted from https://www.math.utah.edu/~pa/math/e.html
-        // We need a high resolution float in order to properly calculate the magic constant
-        let mut euler_minus_2 = BigNum::new("0.7182818284590452353602874713526624977572470936999595749669676277240766303535475945713821785251664274274663919320030599218174135966290435729003342952605956307381323286279434907632338298807531952510190115738341879307021540891499348841675092447614606680822648001684774118537423454424371075390777449920695517027618386062613313845830007520449338265602976067371132007093287091274437470472306969772093101416928368190255151086574637721112523897844250569536967707854499699679468644549059879316368892300987931277361782154249992295763514822082698951936680331825288693984964651058209392398294887933203625094431173012381970684161403970198376793206832823764648042953118023287825098194558153017567173613320698112509961818815930416903515988885193458072738667385894228792284998920868058257492796104841984443634632449684875602336248270419786232090021609");
-        
-        for _ in 0..w {
-            euler_minus_2.multiply(2);
-        }
-        
-        let a = euler_minus_2.truncate();
-        let mut a = BigNum::convert_to_binary(&a);
-        BigNum::binary_odd(&mut a); // I'd better use mut reference 
-        let a = BigNum::convert_binary_to_hex(&a);
-        
-        println!("P{} -> {:?}", w, a);
-
-        // Value of golden ration from http://www2.cs.arizona.edu/icon/oddsends/phi.htm
-        let mut golden_ratio_minus_1 = BigNum::new("0.6180339887498948482045868343656381177203091798057628621354486227052604628189024497072072041893911374847540880753868917521266338622235369317931800607667263544333890865959395829056383226613199282902678806752087668925017116962070322210432162695486262963136144381497587012203408058879544547492461856953648644492410443207713449470495658467885098743394422125448770664780915884607499887124007652170575179788341662562494075890697040002812104276217711177780531531714101170466659914669798731761356006708748071013179523689427521948435305678300228785699782977834784587822891109762500302696156170025046433824377648610283831268330372429267526311653392473167111211588186385133162038400522216579128667529465490681131715993432359734949850904094762132229810172610705961164562990981629055520852479035240602017279974717534277759277862561943208275051312181562");
-        for _ in 0..w {
-            golden_ratio_minus_1.multiply(2);
-        }
-        
-        let a = golden_ratio_minus_1.truncate();
-        let mut a = BigNum::convert_to_binary(&a);
-        BigNum::binary_odd(&mut a); // I'd better use mut reference 
-        let a = BigNum::convert_binary_to_hex(&a);
-        
-        println!("Q{} -> {:?}", w, a);
-        println!("")
-
-    }
-
-    pub fn convert_to_binary(decimal : &String) -> String {
-        // https://stackoverflow.com/questions/11006844/convert-a-very-large-number-from-decimal-string-to-binary-representation
-        
-        let odds_to_one = |v :&String| -> u8 {
-
-            if v.ends_with("1") |
-                v.ends_with("3") |
-                v.ends_with("5") |
-                v.ends_with("7") |
-                v.ends_with("9") {
-                return 1_u8;
-            }
-            else {
-                return 0_u8;
-            }
-        };
-        
-        let div_by_two = |s: &String| -> String {
-            let mut new_s = "".to_string();
-            let mut add = 0;
-
-            for ch in s.as_bytes() {
-                let new_dgt = ((ch - '0' as u8) / 2 as u8) + add;
-                new_s = format!("{}{}", new_s, new_dgt);
-                add = odds_to_one(&format!("{}", ch)) * 5;
-            }
-
-            if new_s != "0" && new_s.starts_with('0') {
-                new_s = String::from(&new_s[1..]);
-            }
-
-            new_s
-        };
-
-        if decimal == "0" {
-            return "0".to_string();
-        }
-        else {
-            let mut ret = "".to_string();
-            let mut d = decimal.clone();
-            while d != "0" {
-                ret = format!("{}{}", odds_to_one(&d), ret);
-                d = div_by_two (&d);
-            }
-
-            return ret.to_string();
-        }
-    }
-
     /// For a binary input this just forces the number to be odd
     /// The input is a String that contains a sequence of 1's and 0's.
     pub fn binary_odd(binary: &mut String) {
@@ -147,71 +248,116 @@ impl BigNum {
         }
     }
 
-    pub fn multiply(&mut  self, num2: u128) {
-
-        let num1: Vec<char> = self.0.chars().collect();
-        let num2: Vec<char> = num2.to_string().chars().collect();
-
-        let num1 = num1.iter().map(|c| *c as u8).collect::<Vec<u8>>();
-        let num2 = num2.iter().map(|c| *c as u8).collect::<Vec<u8>>();
+    fn shl1(&self) -> BigNum {
+        let mut limbs = vec![0_u32; self.0.len() + 1];
+        let mut carry = 0_u32;
+        for (i, &limb) in self.0.iter().enumerate() {
+            limbs[i] = (limb << 1) | carry;
+            carry = limb >> 31;
+        }
+        limbs[self.0.len()] = carry;
+        Self::trim(&mut limbs);
+        BigNum(limbs)
+    }
 
-        let len1 = num1.len();
-        let len2 = num2.len();
+    fn is_zero(&self) -> bool {
+        self.0.iter().all(|&limb| limb == 0)
+    }
 
-        if len1 == 0 || len2 == 0 {
-            self.0 = "0".to_string();
-            return;
+    fn trim(limbs: &mut Vec<u32>) {
+        while limbs.len() > 1 && *limbs.last().unwrap() == 0 {
+            limbs.pop();
         }
+    }
+}
 
-        let mut result = vec![0_u8; len1 + len2];
+impl Ord for BigNum {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.0.len() != other.0.len() {
+            return self.0.len().cmp(&other.0.len());
+        }
+        for (a, b) in self.0.iter().rev().zip(other.0.iter().rev()) {
+            if a != b {
+                return a.cmp(b);
+            }
+        }
+        Ordering::Equal
+    }
+}
 
-        let mut i_n1 = 0;
-        let mut i_n2;
+impl PartialOrd for BigNum {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        let mut dot_index = 0;
+impl Add for BigNum {
+    type Output = BigNum;
 
-        for i in (0..len1).rev() {
-            if num1[i] == '.' as u8 {
-                dot_index = len1 - i - 1;
-                continue;
-            }
+    fn add(self, rhs: Self) -> Self::Output {
+        let len = self.0.len().max(rhs.0.len()) + 1;
+        let mut result = vec![0_u32; len];
+        let mut carry = 0_u64;
 
-            let mut carry =0;
-            let n1 = num1[i] - '0' as u8;
+        for (i, slot) in result.iter_mut().enumerate() {
+            let a = *self.0.get(i).unwrap_or(&0) as u64;
+            let b = *rhs.0.get(i).unwrap_or(&0) as u64;
+            let sum = a + b + carry;
+            *slot = sum as u32;
+            carry = sum >> 32;
+        }
 
-            i_n2 = 0;
+        Self::trim(&mut result);
+        BigNum(result)
+    }
+}
 
-            for j in (0..len2).rev() {
-                let n2 = num2[j] - '0' as u8;
-                let partial_res = n1 * n2 + result[i_n1+i_n2] + carry;
+impl Sub for BigNum {
+    type Output = BigNum;
 
-                carry = partial_res/10;
+    fn sub(self, rhs: Self) -> Self::Output {
+        assert!(self >= rhs, "BigNum subtraction underflow");
 
-                result[i_n1 + i_n2] = partial_res % 10;
+        let mut result = vec![0_u32; self.0.len()];
+        let mut borrow = 0_i64;
 
-                i_n2 += 1;
+        for (i, slot) in result.iter_mut().enumerate() {
+            let a = self.0[i] as i64;
+            let b = *rhs.0.get(i).unwrap_or(&0) as i64;
+            let mut diff = a - b - borrow;
+            if diff < 0 {
+                diff += 1_i64 << 32;
+                borrow = 1;
+            } else {
+                borrow = 0;
             }
-
-            if carry > 0 {
-                result[i_n1 + i_n2] += carry;
-            }
-
-            i_n1 += 1;
+            *slot = diff as u32;
         }
 
-        if dot_index != 0 {
-            result.insert(dot_index, '.' as u8);
-        }
-        result.reverse();
+        Self::trim(&mut result);
+        BigNum(result)
+    }
+}
 
-        result = result.iter().map(|c| { if *c == '.' as u8 {return *c;} else {return *c + '0' as u8;} } ).collect();
+impl Mul for BigNum {
+    type Output = BigNum;
 
-        if let Some(first_pos) = result.iter().position(|c| *c != '0' as u8) {
-            self.0 = String::from_utf8_lossy(&result[first_pos..]).to_string();
-        }
-        else {
-            self.0 = "0".to_string();
+    fn mul(self, rhs: Self) -> Self::Output {
+        let mut result = vec![0_u64; self.0.len() + rhs.0.len()];
+
+        for (i, &a) in self.0.iter().enumerate() {
+            let mut carry = 0_u64;
+            for (j, &b) in rhs.0.iter().enumerate() {
+                let prod = a as u64 * b as u64 + result[i + j] + carry;
+                result[i + j] = prod & 0xFFFF_FFFF;
+                carry = prod >> 32;
+            }
+            result[i + rhs.0.len()] += carry;
         }
+
+        let mut limbs: Vec<u32> = result.into_iter().map(|v| v as u32).collect();
+        Self::trim(&mut limbs);
+        BigNum(limbs)
     }
 }
 
@@ -223,18 +369,41 @@ pub fn div_ceil(numerator: usize, divisor: usize) -> usize {
 mod tests {
     use super::*;
 
+    #[test]
+    fn add_sub_test() {
+        let a = BigNum::from_dec_str("123456789000000000000000000000000000000000000011111111111111111111111");
+        let b = BigNum::from_dec_str("246913578000000000000000000000000000000000000022222222222222222222222");
+        assert_eq!((b.clone() - a.clone()).to_string(), a.to_string());
+        assert_eq!((a.clone() + a.clone()).to_string(), b.to_string());
+    }
+
     #[test]
     fn multiplication_test() {
-        let mut res = BigNum::new("123456789000000000000000000000000000000000000011111111111111111111111.0");
-        res.multiply(20);
-        let expected = "2469135780000000000000000000000000000000000000222222222222222222222220.0".to_string();
+        let a = BigNum::from_dec_str("123456789000000000000000000000000000000000000011111111111111111111111");
+        let res = a * BigNum::from_u64(20);
+        let expected = "2469135780000000000000000000000000000000000000222222222222222222222220".to_string();
         assert_eq!(res.to_string(), expected);
 
-        let mut res = BigNum::new("123456789000000000000000000000000000000000000011111111111111111111111");
-        res.multiply(0);
+        let res = BigNum::from_dec_str("123456789000000000000000000000000000000000000011111111111111111111111")
+            * BigNum::zero();
         assert_eq!(res.to_string(), "0".to_string());
     }
 
+    #[test]
+    fn div_rem_test() {
+        let a = BigNum::from_dec_str("1000000000000000000000000000000000000000");
+        let b = BigNum::from_dec_str("7");
+        let (q, r) = a.div_rem(&b);
+        assert_eq!((q * b).to_string(), "999999999999999999999999999999999999994".to_string());
+        assert_eq!(r.to_string(), "6".to_string());
+    }
+
+    #[test]
+    fn from_dec_str_round_trips() {
+        let n = "987654321098765432109876543210";
+        assert_eq!(BigNum::from_dec_str(n).to_string(), n);
+    }
+
     #[test]
     fn binary_odd_test() {
         let mut val = String::from("100");
@@ -247,8 +416,8 @@ mod tests {
     }
 
     #[test]
-    fn convert_to_binary_test() {
-        assert_eq!("111", BigNum::convert_to_binary(&"7".to_string()));
+    fn to_binary_string_test() {
+        assert_eq!("111", BigNum::from_u64(7).to_binary_string());
     }
 
     #[test]
@@ -258,14 +427,32 @@ mod tests {
 
     #[test]
     fn calc_magic_consts_test() {
-        BigNum::calc_magic_constants(16);
-        BigNum::calc_magic_constants(32);
-        BigNum::calc_magic_constants(64);
-        BigNum::calc_magic_constants(128);
+        // Known values, taken from the RC5/RC6 papers.
+        let expected = [
+            (8_u32, "b7", "9f"),
+            (16, "b7e1", "9e37"),
+            (32, "b7e15163", "9e3779b9"),
+            (64, "b7e151628aed2a6b", "9e3779b97f4a7c15"),
+            (
+                128,
+                "b7e151628aed2a6abf7158809cf4f3c7",
+                "9e3779b97f4a7c15f39cc0605cedc835",
+            ),
+        ];
+        for (w, p, q) in expected {
+            let (computed_p, computed_q) = BigNum::magic_constants_bignum(w);
+            assert_eq!(computed_p.to_hex_string(), p);
+            assert_eq!(computed_q.to_hex_string(), q);
+        }
 
-        // We don't have primitive values to keet the next in a native way
-        BigNum::calc_magic_constants(256);
-        BigNum::calc_magic_constants(512);
+        // The limb-backed BigNum has no width limit, so the sizes RC6-256/512 would need
+        // work the same way as the classic ones above; there's no known-good reference value
+        // to compare against, so just check both still come out odd.
+        for w in [256_u32, 512] {
+            let (p, q) = BigNum::magic_constants_bignum(w);
+            assert_eq!(p.div_rem(&BigNum::from_u64(2)).1.to_string(), "1");
+            assert_eq!(q.div_rem(&BigNum::from_u64(2)).1.to_string(), "1");
+        }
     }
 
     #[test]
@@ -277,4 +464,3 @@ mod tests {
 	}
 
 }
-
@@ -1,14 +1,29 @@
-use std::{ops::{Shl, Add, Sub, Shr, BitAnd, BitXor, BitOr}, u8};
+use std::{ops::{Shl, Add, Sub, Mul, Shr, BitAnd, BitXor, BitOr}, u8};
+
+use crate::hash;
+use crate::utils::{self, BigNum};
 
 pub type LargestType = u128;
 
-#[derive(Copy, Clone, PartialEq)]
+/// Limb capacity of `WordType::Big`, wide enough for the largest word size this crate's
+/// RC5/RC6 variants need (512 bits = 8 `u64` limbs). Fixed-size (rather than a `Vec`) so
+/// `Word` stays `Copy` like every native variant.
+const MAX_BIG_LIMBS: usize = 8;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 enum WordType {
     U8(u8),
     U16(u16),
     U32(u32),
     U64(u64),
     U128(u128),
+    /// A word wider than `LargestType`, stored as little-endian `u64` limbs; `bits` says how
+    /// many of `limbs`'s `bits / 64` low slots are actually significant. Its arithmetic can't
+    /// route through `extract()`'s single-`LargestType` value, so `Add`, `Sub`, the bitwise
+    /// operators and both rotations special-case it against the limb-wise `big_*` helpers at
+    /// the bottom of this file. `Add<u8>`, `Mul`, `ct_eq` and `conditional_select` do not, and
+    /// panic on it.
+    Big { bits: u32, limbs: [u64; MAX_BIG_LIMBS] },
 }
 
 impl WordType {
@@ -19,6 +34,12 @@ impl WordType {
             32 => WordType::U32(value as u32),
             64 => WordType::U64(value as u64),
             128 => WordType::U128(value as u128),
+            bits if bits > 128 && bits % 64 == 0 && bits / 64 <= MAX_BIG_LIMBS as LargestType => {
+                let mut limbs = [0_u64; MAX_BIG_LIMBS];
+                limbs[0] = value as u64;
+                limbs[1] = (value >> 64) as u64;
+                WordType::Big { bits: bits as u32, limbs }
+            }
             _ => panic!("{} word size is not supported", word_size),
         }
     }
@@ -30,6 +51,7 @@ impl WordType {
             WordType::U32(_) => u32::MAX as LargestType,
             WordType::U64(_) => u64::MAX as LargestType,
             WordType::U128(_) => u128::MAX as LargestType,
+            WordType::Big { .. } => panic!("Word::Big doesn't fit in a LargestType; use the big_* limb helpers instead"),
         }
     }
 
@@ -41,6 +63,7 @@ impl WordType {
             WordType::U32(value) => (32, value as LargestType),
             WordType::U64(value) => (64, value as LargestType),
             WordType::U128(value) => (128, value as LargestType),
+            WordType::Big { .. } => panic!("Word::Big doesn't fit in a LargestType; use the big_* limb helpers instead"),
         };
 
         (word_size, value)
@@ -53,11 +76,15 @@ impl WordType {
             WordType::U32(value) => value.to_le_bytes().to_vec(),
             WordType::U64(value) => value.to_le_bytes().to_vec(),
             WordType::U128(value) => value.to_le_bytes().to_vec(),
+            WordType::Big { bits, limbs } => {
+                let num_limbs = (*bits / 64) as usize;
+                limbs[..num_limbs].iter().flat_map(|limb| limb.to_le_bytes()).collect()
+            }
         }
     }
 }
 
-#[derive(PartialEq, Clone, Copy)]
+#[derive(Debug, PartialEq, Clone, Copy)]
 pub struct Word {
     data: WordType,
 }
@@ -71,7 +98,7 @@ impl WordBuilder {
     pub fn new(word_size: LargestType) -> Self {
         Self { word_size }
     }
-    
+
     pub fn build_word(&self, value: LargestType) -> Word {
         Word::new(self.word_size, value)
     }
@@ -80,6 +107,85 @@ impl WordBuilder {
     pub fn new_word_vec(&self, num_words: usize) -> Vec<Word> {
         vec![self.build_word(0) ; num_words]
     }
+
+    /// Parses `bytes` (exactly `word_size / 8` of them) as a single little-endian `Word`.
+    pub fn word_from_le_bytes(&self, bytes: &[u8]) -> Word {
+        let bytes_per_word = (self.word_size / 8) as usize;
+        assert_eq!(bytes.len(), bytes_per_word, "word_from_le_bytes: wrong byte length for this word size");
+
+        if self.word_size > 128 {
+            let limbs: Vec<u64> = bytes
+                .chunks(8)
+                .map(|chunk| {
+                    let mut limb_bytes = [0_u8; 8];
+                    limb_bytes[..chunk.len()].copy_from_slice(chunk);
+                    u64::from_le_bytes(limb_bytes)
+                })
+                .collect();
+            return big_word(self.word_size as u32, limbs);
+        }
+
+        let mut value: LargestType = 0;
+        for &byte in bytes.iter().rev() {
+            value = (value << 8) | byte as LargestType;
+        }
+        self.build_word(value)
+    }
+
+    /// Parses `bytes` (exactly `word_size / 8` of them) as a single big-endian `Word`.
+    pub fn word_from_be_bytes(&self, bytes: &[u8]) -> Word {
+        let reversed: Vec<u8> = bytes.iter().rev().copied().collect();
+        self.word_from_le_bytes(&reversed)
+    }
+
+    /// Chunks `bytes` into a vector of little-endian `Word`s (zero-padding the tail, sized via
+    /// `div_ceil` the same way `RC5`'s key expansion does).
+    pub fn words_from_bytes(&self, bytes: &[u8]) -> Vec<Word> {
+        let bytes_per_word = (self.word_size / 8) as usize;
+        let num_words = utils::div_ceil(bytes.len(), bytes_per_word);
+
+        let mut words = Vec::with_capacity(num_words);
+        for i in 0..num_words {
+            let start = i * bytes_per_word;
+            let end = std::cmp::min(start + bytes_per_word, bytes.len());
+
+            let mut word_bytes = vec![0_u8; bytes_per_word];
+            word_bytes[..end - start].copy_from_slice(&bytes[start..end]);
+            words.push(self.word_from_le_bytes(&word_bytes));
+        }
+
+        words
+    }
+
+    /// The inverse of `words_from_bytes`: concatenates each word's little-endian bytes.
+    pub fn words_to_bytes(words: &[Word]) -> Vec<u8> {
+        words.iter().flat_map(Word::to_le_bytes).collect()
+    }
+
+    /// Derives the initial key-word array `L[]` from a secret of *any* length (e.g. a human
+    /// passphrase), rather than requiring callers to pre-size or truncate it themselves.
+    ///
+    /// Hashes `secret` with SHA-256, then expands that digest into exactly `num_key_bytes` by
+    /// repeatedly hashing `digest || counter` for `counter = 0, 1, 2, ...` and concatenating the
+    /// results (truncating the last block), the same "hash-then-expand" shape as the orion
+    /// crate's SHA-2-based KDFs. The expanded bytes are chunked into words via
+    /// [`WordBuilder::words_from_bytes`], so the result is ready to hand straight to the RC5/RC6
+    /// key schedule.
+    pub fn derive_key_words(&self, secret: &[u8], num_key_bytes: usize) -> Vec<Word> {
+        let digest = hash::sha256(secret);
+
+        let mut expanded = Vec::with_capacity(num_key_bytes);
+        let mut counter: u32 = 0;
+        while expanded.len() < num_key_bytes {
+            let mut block = digest.to_vec();
+            block.extend_from_slice(&counter.to_le_bytes());
+            expanded.extend_from_slice(&hash::sha256(&block));
+            counter += 1;
+        }
+        expanded.truncate(num_key_bytes);
+
+        self.words_from_bytes(&expanded)
+    }
 }
 
 impl Word {
@@ -91,15 +197,69 @@ impl Word {
     }
 
     fn check_types(&self, rhs: Word) {
-        if std::mem::discriminant(&self.data) != 
-            std::mem::discriminant(&rhs.data) {
-                panic!("The types dimension should match in the operation");
+        match (&self.data, &rhs.data) {
+            (WordType::Big { bits: a, .. }, WordType::Big { bits: b, .. }) if a == b => {}
+            _ if std::mem::discriminant(&self.data) == std::mem::discriminant(&rhs.data) => {}
+            _ => panic!("The types dimension should match in the operation"),
         }
     }
 
     pub fn to_le_bytes(&self) -> Vec<u8> {
         self.data.to_le_bytes()
     }
+
+    /// Renders `self` as a big-endian hex string, via `BigNum::convert_binary_to_hex`'s nibble
+    /// table.
+    pub fn to_hex_str(&self) -> String {
+        let mut binary = String::new();
+        for byte in self.to_le_bytes().iter().rev() {
+            binary += &format!("{:08b}", byte);
+        }
+        BigNum::convert_binary_to_hex(&binary).to_lowercase()
+    }
+
+    /// Parses a big-endian hex string into a `word_builder`-sized `Word`, validating that
+    /// `hex` has exactly `word_size / 4` digits.
+    pub fn from_hex_str(word_builder: &WordBuilder, hex: &str) -> Word {
+        let expected_len = (word_builder.word_size / 4) as usize;
+        assert_eq!(hex.len(), expected_len, "from_hex_str: wrong digit count for this word size");
+
+        let be_bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("from_hex_str: invalid hex digit"))
+            .collect();
+
+        word_builder.word_from_be_bytes(&be_bytes)
+    }
+
+    /// Constant-time equality: an all-ones mask if `self == other`, all-zeros otherwise,
+    /// computed without branching on either value.
+    pub fn ct_eq(self, other: Self) -> u8 {
+        self.check_types(other);
+
+        let (_, a) = self.data.extract();
+        let (_, b) = other.data.extract();
+
+        let diff = a ^ b;
+        // `diff | diff.wrapping_neg()` has its top bit set iff `diff != 0` (two's-complement
+        // is-nonzero trick), giving a branch-free equality test.
+        let is_nonzero = ((diff | diff.wrapping_neg()) >> (LargestType::BITS - 1)) as u8;
+        !(0_u8.wrapping_sub(is_nonzero))
+    }
+
+    /// Selects `a` or `b` according to `mask` (as produced by [`Word::ct_eq`]: all-ones picks
+    /// `b`, all-zeros picks `a`) via `(a & !mask) | (b & mask)`, with no data-dependent branch.
+    pub fn conditional_select(a: Self, b: Self, mask: u8) -> Self {
+        a.check_types(b);
+
+        let (word_size, a_value) = a.data.extract();
+        let (_, b_value) = b.data.extract();
+
+        // Broadcasts the 8-bit 0x00/0xFF mask across all 128 bits of `LargestType`.
+        let mask = mask as LargestType * (LargestType::MAX / u8::MAX as LargestType);
+
+        Word::new(word_size, (a_value & !mask) | (b_value & mask))
+    }
 }
 
 impl Shl<Word> for Word {
@@ -108,6 +268,12 @@ impl Shl<Word> for Word {
     fn shl(self, rhs: Word) -> Self::Output {
         self.check_types(rhs);
 
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            let shift = (rhs_limbs[0] % bits as u64) as u32;
+            return big_word(bits, big_rotl(&limbs[..num_limbs], bits, shift));
+        }
+
         let (word_size, self_value) = self.data.extract();
         let (_, rhs_value) = rhs.data.extract();
 
@@ -124,8 +290,14 @@ impl Shl<u8> for Word {
     type Output = Word;
 
     fn shl(self, rhs: u8) -> Self::Output {
+        if let WordType::Big { bits, limbs } = self.data {
+            let num_limbs = (bits / 64) as usize;
+            let shift = rhs as u32 % bits;
+            return big_word(bits, big_rotl(&limbs[..num_limbs], bits, shift));
+        }
+
         let (word_size, self_value) = self.data.extract();
-        
+
         let shift_amount = rhs as LargestType % word_size;
 
         let left = self_value << shift_amount;
@@ -141,6 +313,12 @@ impl Shr<Word> for Word {
     fn shr(self, rhs: Word) -> Self::Output {
         self.check_types(rhs);
 
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            let shift = (rhs_limbs[0] % bits as u64) as u32;
+            return big_word(bits, big_rotr(&limbs[..num_limbs], bits, shift));
+        }
+
         let (word_size, self_value) = self.data.extract();
         let (_, rhs_value) = rhs.data.extract();
 
@@ -159,6 +337,11 @@ impl BitOr for Word {
 	fn bitor(self, rhs: Self) -> Self::Output {
         self.check_types(rhs);
 
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            return big_word(bits, big_or(&limbs[..num_limbs], &rhs_limbs[..num_limbs]));
+        }
+
         let (word_size, self_value) = self.data.extract();
         let (_, rhs_value) = rhs.data.extract();
 
@@ -172,6 +355,11 @@ impl BitAnd for Word {
 	fn bitand(self, rhs: Self) -> Self::Output {
         self.check_types(rhs);
 
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            return big_word(bits, big_and(&limbs[..num_limbs], &rhs_limbs[..num_limbs]));
+        }
+
         let (word_size, self_value) = self.data.extract();
         let (_, rhs_value) = rhs.data.extract();
 
@@ -185,6 +373,11 @@ impl BitXor for Word {
 	fn bitxor(self, rhs: Self) -> Self::Output {
         self.check_types(rhs);
 
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            return big_word(bits, big_xor(&limbs[..num_limbs], &rhs_limbs[..num_limbs]));
+        }
+
         let (word_size, self_value) = self.data.extract();
         let (_, rhs_value) = rhs.data.extract();
 
@@ -198,24 +391,54 @@ impl Add for Word {
     fn add(self, rhs: Self) -> Self::Output {
         self.check_types(rhs);
 
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            return big_word(bits, big_add(&limbs[..num_limbs], &rhs_limbs[..num_limbs], bits));
+        }
+
         let (word_size, self_value) = self.data.extract();
         let (_, rhs_value) = rhs.data.extract();
 
-        let max_val = self.data.max_val() + 1;
+        // `max_val` is `2^word_size - 1`, so masking with it is reduction mod `2^word_size`
+        // without ever computing `max_val + 1` (which overflows `LargestType` at word_size 128).
+        let max_val = self.data.max_val();
 
-        Word::new(word_size, (self_value + rhs_value) % max_val)
+        Word::new(word_size, self_value.wrapping_add(rhs_value) & max_val)
     }
 }
 
 impl Add<u8> for Word {
     type Output = Word;
 
+    // The `& max_val` isn't a second arithmetic op fighting the `+` - `max_val` is
+    // `2^word_size - 1`, so it's the reduction mod `2^word_size` that wrapping addition still
+    // needs applied to it.
+    #[allow(clippy::suspicious_arithmetic_impl)]
     fn add(self, rhs: u8) -> Self::Output {
         let (word_size, self_value) = self.data.extract();
 
-        let max_val = self.data.max_val() + 1;
+        let max_val = self.data.max_val();
+
+        Word::new(word_size, self_value.wrapping_add(rhs as LargestType) & max_val)
+    }
+}
+
+impl Mul for Word {
+    type Output = Word;
+
+    // The `& max_val` isn't a second arithmetic op fighting the `*` - `max_val` is
+    // `2^word_size - 1`, so it's the reduction mod `2^word_size` that wrapping multiplication
+    // still needs applied to it.
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.check_types(rhs);
+
+        let (word_size, self_value) = self.data.extract();
+        let (_, rhs_value) = rhs.data.extract();
+
+        let max_val = self.data.max_val();
 
-        Word::new(word_size, (self_value + rhs as LargestType) % max_val)
+        Word::new(word_size, self_value.wrapping_mul(rhs_value) & max_val)
     }
 }
 
@@ -225,24 +448,148 @@ impl Sub for Word {
 	fn sub(self, rhs: Self) -> Self::Output {
         self.check_types(rhs);
 
-        let (word_size, mut self_value) = self.data.extract();
-        let (_, mut rhs_value) = rhs.data.extract();
+        if let (WordType::Big { bits, limbs }, WordType::Big { limbs: rhs_limbs, .. }) = (self.data, rhs.data) {
+            let num_limbs = (bits / 64) as usize;
+            return big_word(bits, big_sub(&limbs[..num_limbs], &rhs_limbs[..num_limbs], bits));
+        }
+
+        let (word_size, self_value) = self.data.extract();
+        let (_, rhs_value) = rhs.data.extract();
 
         let max_val = self.data.max_val();
 
-        self_value = self_value % ( max_val + 1 );
-        rhs_value = rhs_value % ( max_val + 1 );
+        // Branch-free: no comparison on the (secret-dependent) operands. Two's-complement
+        // wrapping subtraction is already correct mod `2^128`; masking with `max_val` (i.e.
+        // `2^word_size - 1`) reduces it mod `2^word_size` without computing `max_val + 1`
+        // (which overflows `LargestType` at word_size 128).
+        let result = self_value.wrapping_sub(rhs_value) & max_val;
+
+        Word::new(word_size, result)
+    }
+}
+
+/// Builds a `Word::Big` of `bits` bits from `computed` (length `bits / 64`), zero-padding the
+/// rest of the fixed-size limb array.
+fn big_word(bits: u32, computed: Vec<u64>) -> Word {
+    let mut limbs = [0_u64; MAX_BIG_LIMBS];
+    limbs[..computed.len()].copy_from_slice(&computed);
+    Word { data: WordType::Big { bits, limbs } }
+}
+
+/// Truncates `limbs` down to exactly `bits` significant bits, by masking off the high bits of
+/// the top limb.
+fn big_trim_to_bits(limbs: &mut [u64], bits: u32) {
+    let top_bits = bits % 64;
+    if top_bits != 0 {
+        let mask = (1_u64 << top_bits) - 1;
+        let last = limbs.len() - 1;
+        limbs[last] &= mask;
+    }
+}
+
+fn big_and(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(x, y)| x & y).collect()
+}
+
+fn big_or(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(x, y)| x | y).collect()
+}
+
+fn big_xor(a: &[u64], b: &[u64]) -> Vec<u64> {
+    a.iter().zip(b).map(|(x, y)| x ^ y).collect()
+}
+
+/// Limb-wise addition mod `2^bits`: the carry out of the top limb is simply dropped.
+fn big_add(a: &[u64], b: &[u64], bits: u32) -> Vec<u64> {
+    let mut result = vec![0_u64; a.len()];
+    let mut carry = 0_u128;
+    for i in 0..a.len() {
+        let sum = a[i] as u128 + b[i] as u128 + carry;
+        result[i] = sum as u64;
+        carry = sum >> 64;
+    }
+    big_trim_to_bits(&mut result, bits);
+    result
+}
+
+/// Limb-wise subtraction mod `2^bits`: the final borrow (when `a < b`) is simply dropped.
+fn big_sub(a: &[u64], b: &[u64], bits: u32) -> Vec<u64> {
+    let mut result = vec![0_u64; a.len()];
+    let mut borrow = 0_i128;
+    for i in 0..a.len() {
+        let diff = a[i] as i128 - b[i] as i128 - borrow;
+        if diff < 0 {
+            result[i] = (diff + (1_i128 << 64)) as u64;
+            borrow = 1;
+        } else {
+            result[i] = diff as u64;
+            borrow = 0;
+        }
+    }
+    big_trim_to_bits(&mut result, bits);
+    result
+}
+
+/// Logical shift-left by `shift` (`0..bits`): bits shifted off the top are dropped, zeros
+/// shifted in at the bottom.
+fn big_shl(limbs: &[u64], bits: u32, shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return limbs.to_vec();
+    }
+
+    let num_limbs = limbs.len();
+    let limb_shift = (shift / 64) as usize;
+    let bit_shift = shift % 64;
 
-        let result: LargestType;
-        if self_value > rhs_value {
-            result = self_value - rhs_value;
+    let mut result = vec![0_u64; num_limbs];
+    for i in limb_shift..num_limbs {
+        let mut value = limbs[i - limb_shift] << bit_shift;
+        if bit_shift > 0 && i > limb_shift {
+            value |= limbs[i - limb_shift - 1] >> (64 - bit_shift);
         }
-        else {
-            result = max_val - rhs_value + self_value + 1;
+        result[i] = value;
+    }
+    big_trim_to_bits(&mut result, bits);
+    result
+}
+
+/// Logical shift-right by `shift` (`0..bits`): zeros are shifted in at the top.
+fn big_shr(limbs: &[u64], bits: u32, shift: u32) -> Vec<u64> {
+    if shift == 0 {
+        return limbs.to_vec();
+    }
+
+    let num_limbs = limbs.len();
+    let limb_shift = (shift / 64) as usize;
+    let bit_shift = shift % 64;
+
+    let mut result = vec![0_u64; num_limbs];
+    for i in 0..(num_limbs - limb_shift) {
+        let mut value = limbs[i + limb_shift] >> bit_shift;
+        if bit_shift > 0 && i + limb_shift + 1 < num_limbs {
+            value |= limbs[i + limb_shift + 1] << (64 - bit_shift);
         }
+        result[i] = value;
+    }
+    result
+}
 
-        Word::new(word_size, result)
+/// Rotate-left by `shift` bits, mod `bits`: mirrors `Word`'s native `(x << s) | (x >> (bits - s))`.
+fn big_rotl(limbs: &[u64], bits: u32, shift: u32) -> Vec<u64> {
+    let shift = shift % bits;
+    if shift == 0 {
+        return limbs.to_vec();
+    }
+    big_or(&big_shl(limbs, bits, shift), &big_shr(limbs, bits, bits - shift))
+}
+
+/// Rotate-right by `shift` bits, mod `bits`: mirrors `Word`'s native `(x >> s) | (x << (bits - s))`.
+fn big_rotr(limbs: &[u64], bits: u32, shift: u32) -> Vec<u64> {
+    let shift = shift % bits;
+    if shift == 0 {
+        return limbs.to_vec();
     }
+    big_or(&big_shr(limbs, bits, shift), &big_shl(limbs, bits, bits - shift))
 }
 
 #[cfg(test)]
@@ -259,6 +606,19 @@ mod word_test {
 		assert!(wb.build_word(0) == wb.build_word(0x00FF) & wb.build_word(0));
 		assert!(wb.build_word(3) == wb.build_word(1) | wb.build_word(2));
 		assert!(wb.build_word(0xFFFE) == wb.build_word(0xFFFF) ^ wb.build_word(0x0001));
+		assert!(wb.build_word(12) == wb.build_word(3) * wb.build_word(4));
+		assert!(wb.build_word(0x0002) == wb.build_word(0x8001) * wb.build_word(0x0002));
+    }
+
+    #[test]
+    fn constant_time_test() {
+        let wb = WordBuilder::new(16);
+
+        assert_eq!(0xFF, wb.build_word(42).ct_eq(wb.build_word(42)));
+        assert_eq!(0x00, wb.build_word(42).ct_eq(wb.build_word(43)));
+
+        assert!(wb.build_word(1) == Word::conditional_select(wb.build_word(1), wb.build_word(2), 0x00));
+        assert!(wb.build_word(2) == Word::conditional_select(wb.build_word(1), wb.build_word(2), 0xFF));
     }
 
     #[test]
@@ -297,4 +657,68 @@ mod word_test {
 		assert!(wb.build_word(0x0070) == result);
 
 	}
+
+    #[test]
+    fn byte_and_hex_codec_test() {
+        let wb = WordBuilder::new(32);
+
+        let word = wb.build_word(0x01020304);
+        assert_eq!(word, wb.word_from_le_bytes(&word.to_le_bytes()));
+        assert_eq!("01020304", word.to_hex_str());
+        assert_eq!(word, Word::from_hex_str(&wb, "01020304"));
+
+        let be_bytes = [0x01_u8, 0x02, 0x03, 0x04];
+        assert_eq!(word, wb.word_from_be_bytes(&be_bytes));
+
+        let bytes = [0x11_u8, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+        let words = wb.words_from_bytes(&bytes);
+        assert_eq!(2, words.len());
+        let mut round_tripped = WordBuilder::words_to_bytes(&words);
+        round_tripped.truncate(bytes.len());
+        assert_eq!(&bytes[..], &round_tripped[..]);
+    }
+
+    #[test]
+    fn derive_key_words_test() {
+        let wb = WordBuilder::new(32);
+
+        // Deterministic: the same secret always derives the same key words.
+        let a = wb.derive_key_words(b"correct horse battery staple", 16);
+        let b = wb.derive_key_words(b"correct horse battery staple", 16);
+        assert_eq!(a.len(), 4);
+        assert!(a.iter().zip(&b).all(|(x, y)| *x == *y));
+
+        // Different secrets derive different key material.
+        let c = wb.derive_key_words(b"a completely different secret", 16);
+        assert!(a.iter().zip(&c).any(|(x, y)| *x != *y));
+
+        // Expansion past a single SHA-256 digest (32 bytes) still produces exactly the
+        // requested number of key bytes, chunked into the right number of words.
+        let long = wb.derive_key_words(b"secret", 48);
+        assert_eq!(long.len(), 12);
+    }
+
+    #[test]
+    fn big_word_test() {
+        let wb = WordBuilder::new(256);
+
+        assert!(wb.build_word(3) == wb.build_word(1) + wb.build_word(2));
+        assert!(wb.build_word(1) == wb.build_word(3) - wb.build_word(2));
+        assert!(wb.build_word(0b110) == wb.build_word(0b101) ^ wb.build_word(0b011));
+        assert!(wb.build_word(0b001) == wb.build_word(0b101) & wb.build_word(0b011));
+        assert!(wb.build_word(0b111) == wb.build_word(0b101) | wb.build_word(0b011));
+
+        assert!(wb.build_word(4) == wb.build_word(1) << wb.build_word(2));
+        assert!(wb.build_word(1) == wb.build_word(4) >> wb.build_word(2));
+
+        // Rotating the top bit all the way around a 256-bit word should land back on 1.
+        let top_bit = wb.build_word(1) << wb.build_word(255);
+        assert!(wb.build_word(1) == top_bit << wb.build_word(1));
+
+        assert_eq!(32, wb.build_word(0).to_le_bytes().len());
+
+        let wb512 = WordBuilder::new(512);
+        assert!(wb512.build_word(3) == wb512.build_word(1) + wb512.build_word(2));
+        assert_eq!(64, wb512.build_word(0).to_le_bytes().len());
+    }
 }